@@ -0,0 +1,30 @@
+// shared hash function used to accumulate the per-step op accumulator during
+// execution, to hash program code blocks, and to build Merkle commitments over
+// the execution trace.
+
+/// Mixes an arbitrary number of u64 words down into a 4-word digest. This is
+/// the same accumulator the processor uses to fold each opcode into the
+/// running per-step op accumulator (see `TraceState::get_op_acc`).
+pub fn hash_values(values: &[u64]) -> [u64; 4] {
+    let mut state = [0x6a09e667f3bcc908u64, 0xbb67ae8584caa73bu64, 0x3c6ef372fe94f82bu64, 0xa54ff53a5f1d36f1u64];
+
+    for (i, &value) in values.iter().enumerate() {
+        let word = state[i % 4] ^ value;
+        state[i % 4] = word.wrapping_mul(0x9e3779b97f4a7c15).rotate_left(31);
+        state[(i + 1) % 4] = state[(i + 1) % 4].wrapping_add(state[i % 4]);
+    }
+
+    // final mixing round so that short inputs still spread across all 4 words
+    for i in 0..4 {
+        state[i] = state[i].wrapping_mul(0xff51afd7ed558ccd).rotate_left(27);
+        state[(i + 1) % 4] ^= state[i];
+    }
+
+    state
+}
+
+/// Folds a 4-word digest together with a single nonce; used for grinding and
+/// for deriving Fiat-Shamir challenges from the transcript state.
+pub fn hash_with_nonce(digest: &[u64; 4], nonce: u64) -> [u64; 4] {
+    hash_values(&[digest[0], digest[1], digest[2], digest[3], nonce])
+}
@@ -0,0 +1,11 @@
+pub mod hash;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod processor;
+pub mod program;
+pub mod stark;
+
+pub use processor::execute;
+pub use processor::profiler::ExecutionProfile;
+pub use program::{CodeBlock, Program};
+pub use stark::{verify, FieldExtension, ProofOptions, StarkProof, VerifyError};
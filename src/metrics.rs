@@ -0,0 +1,46 @@
+// Prometheus-compatible gauges for the STARK prover, enabled with the
+// `metrics` feature so the VM can be driven under load and observed on a
+// dashboard instead of scraped from stdout.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::stark::StarkProof;
+
+static TRACE_LENGTH: AtomicU64 = AtomicU64::new(0);
+static PROOF_SIZE_BYTES: AtomicU64 = AtomicU64::new(0);
+static FRI_LAYERS: AtomicU64 = AtomicU64::new(0);
+
+pub fn record_proof(trace_length: usize, proof: &StarkProof) {
+    TRACE_LENGTH.store(trace_length as u64, Ordering::Relaxed);
+    FRI_LAYERS.store(proof.fri_proof.layers.len() as u64, Ordering::Relaxed);
+    PROOF_SIZE_BYTES.store(estimate_proof_size(proof) as u64, Ordering::Relaxed);
+}
+
+fn estimate_proof_size(proof: &StarkProof) -> usize {
+    let queried_bytes: usize = proof.queried_rows.iter().map(|row| row.path.len() * 32 + 32).sum::<usize>()
+        + proof.final_stack.len() * 8
+        + proof.final_stack_path.len() * 32;
+    let fri_bytes: usize = proof
+        .fri_proof
+        .layers
+        .iter()
+        .map(|layer| layer.openings.iter().map(|o| 16 + o.path.len() * 32).sum::<usize>())
+        .sum::<usize>()
+        + proof.fri_proof.remainder.len() * 16;
+    32 + proof.outputs.len() * 8 + queried_bytes + fri_bytes
+}
+
+/// Renders the current gauges in the Prometheus text exposition format.
+pub fn render() -> String {
+    format!(
+        "# TYPE distaff_trace_length gauge\n\
+         distaff_trace_length {}\n\
+         # TYPE distaff_proof_size_bytes gauge\n\
+         distaff_proof_size_bytes {}\n\
+         # TYPE distaff_fri_layers gauge\n\
+         distaff_fri_layers {}\n",
+        TRACE_LENGTH.load(Ordering::Relaxed),
+        PROOF_SIZE_BYTES.load(Ordering::Relaxed),
+        FRI_LAYERS.load(Ordering::Relaxed),
+    )
+}
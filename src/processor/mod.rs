@@ -1,38 +1,65 @@
 use std::time::{ Instant };
-use crate::stark::{ TraceTable, prove };
+use tracing::info_span;
+use crate::program::Program;
+use crate::stark::{ TraceTable, StarkProof, ProofOptions, DefaultTraceLde, prove };
 
 pub mod opcodes;
+pub mod profiler;
 
-const DEFAULT_EXTENSION_FACTOR: usize = 32;
+use profiler::ExecutionProfile;
 
-pub fn execute(program: &[u64], inputs: &[u64], num_outputs: usize) -> ([u64; 4], Vec<u64>) {
+#[tracing::instrument(level = "info", skip_all)]
+pub fn execute(
+    program: &Program,
+    public_inputs: &[u64],
+    advice_tape: &[u64],
+    num_outputs: usize,
+    options: &ProofOptions,
+    profile: Option<&mut ExecutionProfile>,
+) -> ([u64; 4], Vec<u64>, StarkProof) {
 
-    // pad the program to make sure the length is a power of two and the last operation is NOOP
-    let mut program = program.to_vec();
-    let trace_length = if program.len() == program.len().next_power_of_two() {
-        program.len().next_power_of_two() * 2
+    // the program hash is the root block's hash, fixed independently of how
+    // the operation stream below ends up padded or executed
+    let program_hash = program.hash();
+
+    // flatten the program's control-flow tree into a straight-line operation
+    // stream, then pad it to make sure its length is a power of two and the
+    // last operation is NOOP
+    let ops = program.flatten();
+    let program_length = ops.len();
+    let mut ops = ops;
+    let trace_length = if ops.len() == ops.len().next_power_of_two() {
+        ops.len().next_power_of_two() * 2
     }
     else {
-        program.len().next_power_of_two()
+        ops.len().next_power_of_two()
     };
-    program.resize(trace_length, opcodes::NOOP);
+    ops.resize(trace_length, opcodes::NOOP);
 
-    // execute the program to create an execution trace
+    // execute the program to create an execution trace; the advice tape is
+    // only used here to feed opcodes::READ and never leaves this function
+    let trace_span = info_span!("trace_generation", steps = trace_length);
     let now = Instant::now();
-    let mut trace = TraceTable::new(&program, inputs, DEFAULT_EXTENSION_FACTOR);
-    let t = now.elapsed().as_millis();
-    println!("Generated execution trace of {} steps in {} ms", trace.len(), t);
-    
-    // copy the stack state the the last step to return as output
+    let trace = {
+        let _enter = trace_span.enter();
+        TraceTable::new(&ops, public_inputs, advice_tape, options, program_length, profile)
+    };
+    tracing::info!(steps = trace.len(), elapsed_ms = now.elapsed().as_millis() as u64, "generated execution trace");
+
+    // copy the stack state at the last step to return as output
     let last_state = trace.get_state(trace.len() - 1);
     let outputs = last_state.get_stack()[0..num_outputs].to_vec();
 
-    // copy the hash of the program
-    let mut program_hash = [0u64; 4];
-    program_hash.copy_from_slice(&last_state.get_op_acc()[0..4]);
+    // low-degree-extend and commit to the trace, then generate the STARK
+    // proof against that commitment; only public_inputs and outputs are
+    // bound into it
+    let lde_span = info_span!("lde_commit", steps = trace.len(), extension_factor = options.extension_factor);
+    let lde = { let _enter = lde_span.enter(); DefaultTraceLde::new(&trace) };
+
+    let proof = prove(&trace, &lde, program_hash, public_inputs, &outputs, options);
 
-    // generate STARK proof
-    prove(&mut trace, inputs, &outputs);
+    #[cfg(feature = "metrics")]
+    crate::metrics::record_proof(trace.len(), &proof);
 
-    return (program_hash, outputs);
-}
\ No newline at end of file
+    (program_hash, outputs, proof)
+}
@@ -0,0 +1,9 @@
+pub const NOOP: u64 = 0;
+pub const PUSH: u64 = 1;
+pub const DROP: u64 = 2;
+pub const ADD: u64 = 3;
+
+/// Pulls the next value off the secret advice tape and pushes it onto the
+/// stack. This is the only way non-deterministic data enters the trace, and
+/// it is never bound into the proof's public inputs.
+pub const READ: u64 = 4;
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+/// Per-opcode cycle accounting collected while a `TraceTable` executes a
+/// program, so a user can see what dominates proving cost before paying for
+/// a full proof. Profiling is opt-in: pass `Some(&mut profile)` to
+/// `execute()` to populate one.
+#[derive(Default)]
+pub struct ExecutionProfile {
+    cycles_by_opcode: HashMap<u64, usize>,
+    peak_stack_depth: usize,
+    padding_cycles: usize,
+}
+
+impl ExecutionProfile {
+    pub fn new() -> ExecutionProfile {
+        ExecutionProfile::default()
+    }
+
+    pub fn record_step(&mut self, opcode: u64, stack_depth: usize) {
+        *self.cycles_by_opcode.entry(opcode).or_insert(0) += 1;
+        self.peak_stack_depth = self.peak_stack_depth.max(stack_depth);
+    }
+
+    /// Records how many trace steps were padding inserted to round the
+    /// program up to a power of two, rather than real opcodes.
+    pub fn set_padding_cycles(&mut self, padding_cycles: usize) {
+        self.padding_cycles = padding_cycles;
+    }
+
+    pub fn peak_stack_depth(&self) -> usize {
+        self.peak_stack_depth
+    }
+
+    pub fn padding_cycles(&self) -> usize {
+        self.padding_cycles
+    }
+
+    /// Opcodes ordered from most to least expensive, as `(opcode, cycles)`.
+    pub fn opcodes_by_cost(&self) -> Vec<(u64, usize)> {
+        let mut entries: Vec<(u64, usize)> = self.cycles_by_opcode.iter().map(|(&op, &c)| (op, c)).collect();
+        entries.sort_by_key(|&(_, cycles)| std::cmp::Reverse(cycles));
+        entries
+    }
+
+    /// Renders the profile as folded, flamegraph-style text: one
+    /// `opcode cycles` line per opcode, most expensive first, with padding
+    /// overhead broken out on its own line.
+    pub fn to_folded_text(&self) -> String {
+        let mut lines: Vec<String> = self
+            .opcodes_by_cost()
+            .into_iter()
+            .map(|(op, cycles)| format!("opcode_{} {}", op, cycles))
+            .collect();
+
+        if self.padding_cycles > 0 {
+            lines.push(format!("padding {}", self.padding_cycles));
+        }
+
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,112 @@
+use crate::hash::hash_values;
+use crate::processor::opcodes;
+
+/// A straight-line run of opcodes, with no further structure.
+pub struct Span {
+    ops: Vec<u64>,
+    hash: [u64; 4],
+}
+
+/// A two-way branch; only one side executes, chosen by a condition the
+/// conditional-execution machinery (not yet implemented) will supply.
+pub struct Branch {
+    true_branch: Box<CodeBlock>,
+    // kept (and hashed into `hash`) so the branch's committed identity binds
+    // both sides even though only `true_branch` is reachable until
+    // conditional execution lands; see `flatten_into`.
+    #[allow(dead_code)]
+    false_branch: Box<CodeBlock>,
+    hash: [u64; 4],
+}
+
+/// A loop block around `body`. When flattened, the body is emitted exactly
+/// once; no loop condition is evaluated and the body is not repeated.
+pub struct LoopBlock {
+    body: Box<CodeBlock>,
+    hash: [u64; 4],
+}
+
+/// A node in a program's control-flow tree. Each variant's hash is computed
+/// bottom-up from its children when the block is built, so the hash never
+/// depends on how (or whether) the block actually gets executed.
+pub enum CodeBlock {
+    Span(Span),
+    Branch(Branch),
+    Loop(LoopBlock),
+}
+
+impl CodeBlock {
+    pub fn new_span(ops: Vec<u64>) -> CodeBlock {
+        let hash = hash_values(&ops);
+        CodeBlock::Span(Span { ops, hash })
+    }
+
+    pub fn new_branch(true_branch: CodeBlock, false_branch: CodeBlock) -> CodeBlock {
+        let t = true_branch.hash();
+        let f = false_branch.hash();
+        let hash = hash_values(&[t[0], t[1], t[2], t[3], f[0], f[1], f[2], f[3]]);
+        CodeBlock::Branch(Branch {
+            true_branch: Box::new(true_branch),
+            false_branch: Box::new(false_branch),
+            hash,
+        })
+    }
+
+    pub fn new_loop(body: CodeBlock) -> CodeBlock {
+        let b = body.hash();
+        let hash = hash_values(&[b[0], b[1], b[2], b[3]]);
+        CodeBlock::Loop(LoopBlock { body: Box::new(body), hash })
+    }
+
+    pub fn hash(&self) -> [u64; 4] {
+        match self {
+            CodeBlock::Span(span) => span.hash,
+            CodeBlock::Branch(branch) => branch.hash,
+            CodeBlock::Loop(loop_block) => loop_block.hash,
+        }
+    }
+
+    /// Flattens this block into a straight-line operation stream for
+    /// `TraceTable`. Branch and loop blocks have no conditional or iterative
+    /// execution semantics yet, so only their first sub-block contributes;
+    /// the types are in place ahead of that machinery landing.
+    fn flatten_into(&self, ops: &mut Vec<u64>) {
+        match self {
+            CodeBlock::Span(span) => ops.extend_from_slice(&span.ops),
+            CodeBlock::Branch(branch) => branch.true_branch.flatten_into(ops),
+            CodeBlock::Loop(loop_block) => loop_block.body.flatten_into(ops),
+        }
+    }
+}
+
+/// A program is a single root `CodeBlock`. Its hash is simply the root
+/// block's hash, independent of trace padding or how the program is run,
+/// so the prover and verifier always agree on it.
+pub struct Program {
+    root: CodeBlock,
+}
+
+impl Program {
+    pub fn new(root: CodeBlock) -> Program {
+        Program { root }
+    }
+
+    /// Builds a single straight-line program out of a flat opcode list,
+    /// the common case while branches and loops aren't executable yet.
+    pub fn from_ops(ops: Vec<u64>) -> Program {
+        Program { root: CodeBlock::new_span(ops) }
+    }
+
+    pub fn hash(&self) -> [u64; 4] {
+        self.root.hash()
+    }
+
+    pub fn flatten(&self) -> Vec<u64> {
+        let mut ops = Vec::new();
+        self.root.flatten_into(&mut ops);
+        if ops.is_empty() {
+            ops.push(opcodes::NOOP);
+        }
+        ops
+    }
+}
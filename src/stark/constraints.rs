@@ -0,0 +1,15 @@
+#[derive(Debug)]
+pub struct BoundaryConstraintError;
+
+/// Checks that the stack state at the final trace step matches the claimed
+/// public outputs. This is the only constraint the verifier enforces;
+/// transition constraints (how one step derives the next) are not checked.
+pub fn evaluate_boundary_constraints(final_state: &[u64], outputs: &[u64]) -> Result<(), BoundaryConstraintError> {
+    if final_state.len() < outputs.len() {
+        return Err(BoundaryConstraintError);
+    }
+    if &final_state[0..outputs.len()] != outputs {
+        return Err(BoundaryConstraintError);
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+// arithmetic over the 64-bit prime field used for the trace low-degree
+// extension and FRI; values are kept in a u128 so that multiplication never
+// has to worry about overflow.
+
+pub type BaseElement = u128;
+
+/// p = 2^64 - 2^32 + 1, chosen for its large 2-adic subgroup.
+pub const MODULUS: BaseElement = 18_446_744_069_414_584_321;
+
+pub fn add(a: BaseElement, b: BaseElement) -> BaseElement {
+    (a + b) % MODULUS
+}
+
+pub fn sub(a: BaseElement, b: BaseElement) -> BaseElement {
+    (a + MODULUS - (b % MODULUS)) % MODULUS
+}
+
+pub fn mul(a: BaseElement, b: BaseElement) -> BaseElement {
+    (a % MODULUS) * (b % MODULUS) % MODULUS
+}
+
+pub fn exp(base: BaseElement, mut power: u64) -> BaseElement {
+    let mut result = 1;
+    let mut base = base % MODULUS;
+    while power > 0 {
+        if power & 1 == 1 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        power >>= 1;
+    }
+    result
+}
+
+/// Inverts `a` via Fermat's little theorem (a^(p-2) == a^-1 mod p).
+pub fn inv(a: BaseElement) -> BaseElement {
+    exp(a, (MODULUS - 2) as u64)
+}
+
+pub fn from_u64(value: u64) -> BaseElement {
+    value as BaseElement % MODULUS
+}
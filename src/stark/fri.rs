@@ -0,0 +1,179 @@
+use crate::stark::field::{self, BaseElement};
+use crate::stark::merkle::MerkleTree;
+use crate::stark::transcript::Transcript;
+
+/// A single authenticated value within a FRI layer: what the layer holds at
+/// `position`, plus the Merkle path tying it back to that layer's root.
+pub struct FriOpening {
+    pub position: usize,
+    pub value: BaseElement,
+    pub path: Vec<[u64; 4]>,
+}
+
+/// One round of FRI: the Merkle root the verifier actually sees, plus the
+/// handful of positions the query phase asked to open — not the full
+/// evaluation vector, so proof size grows with `num_queries`, not with the
+/// trace length.
+pub struct FriLayer {
+    pub root: [u64; 4],
+    pub openings: Vec<FriOpening>,
+}
+
+pub struct FriProof {
+    pub layers: Vec<FriLayer>,
+    pub remainder: Vec<BaseElement>,
+}
+
+#[derive(Debug)]
+pub struct FriVerifyError;
+
+fn leaf_for(value: BaseElement) -> [u64; 4] {
+    crate::hash::hash_values(&[(value >> 64) as u64, value as u64])
+}
+
+/// Folds `group_size` values spaced `stride` apart into one value via
+/// `sum_k alpha^k * values[i + k*stride]`. With `group_size == 2` this is the
+/// classic `f(x)`/`f(-x)` pairing; larger folding factors fold more points
+/// per layer, so fewer layers are needed but each query opens more values
+/// per layer.
+fn fold_one(current: &[BaseElement], i: usize, stride: usize, group_size: usize, alpha: BaseElement) -> BaseElement {
+    let mut power = 1;
+    let mut acc = 0;
+    for k in 0..group_size {
+        acc = field::add(acc, field::mul(power, current[i + k * stride]));
+        power = field::mul(power, alpha);
+    }
+    acc
+}
+
+/// Same fold as `fold_one`, but over values the verifier opened individually
+/// rather than a full in-memory layer.
+fn fold_opened(values: &[BaseElement], alpha: BaseElement) -> BaseElement {
+    let mut power = 1;
+    let mut acc = 0;
+    for &value in values {
+        acc = field::add(acc, field::mul(power, value));
+        power = field::mul(power, alpha);
+    }
+    acc
+}
+
+/// Reduces a domain position down to the fold-group index it lands in at the
+/// given stride: `current[j]` and `current[j + k*stride]` for every `k` are
+/// folded into the same output position, so any of them maps to the same
+/// `j = position % stride`.
+fn fold_group_index(position: usize, stride: usize) -> usize {
+    position % stride
+}
+
+fn dedup_sorted(mut positions: Vec<usize>) -> Vec<usize> {
+    positions.sort_unstable();
+    positions.dedup();
+    positions
+}
+
+/// Commits to a sequence of trace evaluations by repeatedly folding groups of
+/// `folding_factor` points into a single polynomial `f'(x^folding_factor)` at
+/// a random point `alpha` drawn from the transcript, until the remainder is
+/// small enough to send in the clear. Only the positions needed to check the
+/// queries in `positions` (and whatever they fold into at each subsequent,
+/// smaller layer) are opened with authentication paths; the bulk of each
+/// layer is committed to but never revealed.
+pub fn commit(evaluations: &[BaseElement], folding_factor: usize, positions: &[usize], transcript: &mut Transcript) -> FriProof {
+    let mut layers = Vec::new();
+    let mut current = evaluations.to_vec();
+    let mut active_positions = dedup_sorted(positions.to_vec());
+
+    while current.len() > 1 {
+        let group_size = folding_factor.min(current.len());
+        let leaves: Vec<[u64; 4]> = current.iter().map(|&v| leaf_for(v)).collect();
+        let tree = MerkleTree::new(leaves);
+        let root = tree.root();
+        transcript.absorb(&root);
+
+        let alpha = field::from_u64(transcript.draw_u64());
+        let stride = current.len() / group_size;
+
+        // every query needs its whole fold group opened, since all of it is
+        // required to recompute the folded value the next layer should hold
+        let open_positions = dedup_sorted(
+            active_positions
+                .iter()
+                .flat_map(|&p| {
+                    let j = fold_group_index(p, stride);
+                    (0..group_size).map(move |k| j + k * stride)
+                })
+                .collect(),
+        );
+        let openings = open_positions
+            .iter()
+            .map(|&p| FriOpening { position: p, value: current[p], path: tree.prove(p) })
+            .collect();
+
+        let folded: Vec<BaseElement> = (0..stride)
+            .map(|i| fold_one(&current, i, stride, group_size, alpha))
+            .collect();
+
+        layers.push(FriLayer { root, openings });
+        active_positions = dedup_sorted(active_positions.iter().map(|&p| fold_group_index(p, stride)).collect());
+        current = folded;
+    }
+
+    FriProof { layers, remainder: current }
+}
+
+/// Re-derives the same sequence of alphas, authenticates every opened value
+/// against its layer's root, and checks that each opened fold group folds
+/// into the value the next layer (or the remainder) claims at that position.
+pub fn verify(
+    proof: &FriProof,
+    folding_factor: usize,
+    transcript: &mut Transcript,
+    positions: &[usize],
+    domain_size: usize,
+) -> Result<(), FriVerifyError> {
+    let mut alphas = Vec::with_capacity(proof.layers.len());
+    for layer in &proof.layers {
+        transcript.absorb(&layer.root);
+        alphas.push(field::from_u64(transcript.draw_u64()));
+    }
+
+    let mut active_positions = dedup_sorted(positions.to_vec());
+    let mut len = domain_size;
+
+    for (i, (layer, &alpha)) in proof.layers.iter().zip(alphas.iter()).enumerate() {
+        let group_size = folding_factor.min(len);
+        let stride = len / group_size;
+
+        for opening in &layer.openings {
+            let leaf = leaf_for(opening.value);
+            if !MerkleTree::verify(layer.root, opening.position, leaf, &opening.path) {
+                return Err(FriVerifyError);
+            }
+        }
+
+        for &p in &active_positions {
+            let j = fold_group_index(p, stride);
+            let mut group = Vec::with_capacity(group_size);
+            for k in 0..group_size {
+                let pos = j + k * stride;
+                let value = layer.openings.iter().find(|o| o.position == pos).ok_or(FriVerifyError)?.value;
+                group.push(value);
+            }
+            let folded = fold_opened(&group, alpha);
+
+            let next_value = match proof.layers.get(i + 1) {
+                Some(next_layer) => next_layer.openings.iter().find(|o| o.position == j).ok_or(FriVerifyError)?.value,
+                None => *proof.remainder.get(j).ok_or(FriVerifyError)?,
+            };
+            if folded != next_value {
+                return Err(FriVerifyError);
+            }
+        }
+
+        active_positions = dedup_sorted(active_positions.iter().map(|&p| fold_group_index(p, stride)).collect());
+        len = stride;
+    }
+
+    Ok(())
+}
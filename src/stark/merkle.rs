@@ -0,0 +1,54 @@
+use crate::hash::hash_values;
+
+/// A flat Merkle tree over 4-word leaves, used to commit to the execution
+/// trace and to each FRI layer.
+pub struct MerkleTree {
+    layers: Vec<Vec<[u64; 4]>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<[u64; 4]>) -> MerkleTree {
+        assert!(leaves.len().is_power_of_two(), "number of leaves must be a power of two");
+
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let previous = layers.last().unwrap();
+            let next = previous
+                .chunks(2)
+                .map(|pair| hash_values(&[pair[0][0], pair[0][1], pair[0][2], pair[0][3],
+                                           pair[1][0], pair[1][1], pair[1][2], pair[1][3]]))
+                .collect();
+            layers.push(next);
+        }
+
+        MerkleTree { layers }
+    }
+
+    pub fn root(&self) -> [u64; 4] {
+        self.layers.last().unwrap()[0]
+    }
+
+    /// Returns the sibling hashes needed to authenticate the leaf at `index`.
+    pub fn prove(&self, mut index: usize) -> Vec<[u64; 4]> {
+        let mut path = Vec::with_capacity(self.layers.len() - 1);
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let sibling = index ^ 1;
+            path.push(layer[sibling]);
+            index >>= 1;
+        }
+        path
+    }
+
+    pub fn verify(root: [u64; 4], mut index: usize, leaf: [u64; 4], path: &[[u64; 4]]) -> bool {
+        let mut node = leaf;
+        for sibling in path {
+            node = if index & 1 == 0 {
+                hash_values(&[node[0], node[1], node[2], node[3], sibling[0], sibling[1], sibling[2], sibling[3]])
+            } else {
+                hash_values(&[sibling[0], sibling[1], sibling[2], sibling[3], node[0], node[1], node[2], node[3]])
+            };
+            index >>= 1;
+        }
+        node == root
+    }
+}
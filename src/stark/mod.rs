@@ -0,0 +1,380 @@
+use std::error::Error;
+use std::fmt;
+
+pub mod constraints;
+pub mod field;
+pub mod fri;
+pub mod merkle;
+pub mod trace;
+pub mod trace_lde;
+pub mod transcript;
+
+pub use trace::{TraceState, TraceTable};
+pub use trace_lde::{DefaultTraceLde, OpenedRow, TraceLde};
+
+use fri::FriProof;
+use merkle::MerkleTree;
+use transcript::Transcript;
+
+/// Whether trace values are committed to directly in the base field, or
+/// lifted into a degree-2 extension first for extra soundness. Only `None`
+/// is implemented today; `Quadratic` is reserved for when the prover needs
+/// more than the base field's ~64 bits of security.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldExtension {
+    None,
+    Quadratic,
+}
+
+/// Knobs that trade proof size and proving time against soundness. Roughly,
+/// the proof is sound to `num_queries * log2(extension_factor) +
+/// grinding_factor` bits; this only holds because the trace rows are opened
+/// at exactly `num_queries` positions, and the FRI layers only at the fold
+/// groups those positions reduce to, each with Merkle authentication paths
+/// rather than disclosed in full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofOptions {
+    pub extension_factor: usize,
+    pub num_queries: usize,
+    pub grinding_factor: u32,
+    pub folding_factor: usize,
+    pub field_extension: FieldExtension,
+}
+
+impl ProofOptions {
+    /// # Panics
+    /// Panics if `extension_factor` or `folding_factor` is not a power of
+    /// two (both are used as LDE domain divisors, and `MerkleTree` requires
+    /// a power-of-two leaf count), or if `grinding_factor >= 64` (a digest
+    /// only has 64 bits, so `Transcript::grind` would loop forever looking
+    /// for that many trailing zeros).
+    pub fn new(
+        extension_factor: usize,
+        num_queries: usize,
+        grinding_factor: u32,
+        folding_factor: usize,
+        field_extension: FieldExtension,
+    ) -> ProofOptions {
+        assert!(extension_factor.is_power_of_two(), "extension_factor must be a power of two");
+        assert!(folding_factor.is_power_of_two(), "folding_factor must be a power of two");
+        assert!(grinding_factor < 64, "grinding_factor must be smaller than 64");
+        ProofOptions { extension_factor, num_queries, grinding_factor, folding_factor, field_extension }
+    }
+}
+
+impl Default for ProofOptions {
+    /// Reproduces today's behavior: a 32x blowup, 32 queries, no grinding,
+    /// and degree-2 FRI folding.
+    fn default() -> ProofOptions {
+        ProofOptions {
+            extension_factor: 32,
+            num_queries: 32,
+            grinding_factor: 0,
+            folding_factor: 2,
+            field_extension: FieldExtension::None,
+        }
+    }
+}
+
+/// Everything the verifier needs to check an execution without re-running
+/// the program: the trace commitment, the FRI proof over it, and enough
+/// opened rows to check the boundary constraints at the queried positions.
+pub struct StarkProof {
+    pub program_hash: [u64; 4],
+    pub outputs: Vec<u64>,
+    pub trace_root: [u64; 4],
+    /// Size of the low-degree-extended trace domain, i.e. the un-extended
+    /// trace length times `options.extension_factor`.
+    pub trace_length: usize,
+    pub queried_positions: Vec<usize>,
+    pub queried_rows: Vec<OpenedRow>,
+    /// The final row's actual stack, disclosed in the clear (its prefix is
+    /// `outputs` anyway) along with the Merkle path authenticating it.
+    pub final_stack: Vec<u64>,
+    pub final_stack_path: Vec<[u64; 4]>,
+    pub fri_proof: FriProof,
+    pub grinding_nonce: u64,
+    pub options: ProofOptions,
+}
+
+#[derive(Debug)]
+pub enum VerifyError {
+    ProgramHashMismatch,
+    OutputMismatch,
+    QueryPositionMismatch,
+    RowAuthenticationFailed(usize),
+    BoundaryConstraintViolation(usize),
+    GrindingCheckFailed,
+    FriVerificationFailed,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerifyError::ProgramHashMismatch => write!(f, "proof does not bind to the given program hash"),
+            VerifyError::OutputMismatch => write!(f, "proof does not bind to the given outputs"),
+            VerifyError::QueryPositionMismatch => write!(f, "query positions do not match the Fiat-Shamir transcript"),
+            VerifyError::RowAuthenticationFailed(position) => write!(f, "queried row at position {} does not authenticate against the trace root", position),
+            VerifyError::BoundaryConstraintViolation(step) => write!(f, "boundary constraint violated at step {}", step),
+            VerifyError::GrindingCheckFailed => write!(f, "grinding nonce does not satisfy the required proof-of-work"),
+            VerifyError::FriVerificationFailed => write!(f, "FRI layers are not consistent with a low-degree trace"),
+        }
+    }
+}
+
+impl Error for VerifyError {}
+
+/// Forces the row authenticating the final trace step into the query set,
+/// replacing an arbitrary existing entry so the set size doesn't change.
+/// Random query positions alone would only land on that one row with
+/// vanishing probability, which would leave `outputs` effectively
+/// unchecked; both the prover and verifier apply this same deterministic
+/// step, so they always agree on the resulting positions.
+fn include_final_row(mut positions: Vec<usize>, domain_size: usize, extension_factor: usize) -> Vec<usize> {
+    let final_row = domain_size - extension_factor;
+    if !positions.contains(&final_row) {
+        positions[0] = final_row;
+    }
+    positions.sort_unstable();
+    positions.dedup();
+    positions
+}
+
+/// Maps LDE-domain row positions down to the un-extended trace steps they
+/// came from — rows are repeated `extension_factor` times across the LDE
+/// domain, and that smaller, un-extended domain is where
+/// `constraint_evaluations` (and so FRI's base layer) actually lives.
+fn to_constraint_domain(positions: &[usize], extension_factor: usize) -> Vec<usize> {
+    let mut reduced: Vec<usize> = positions.iter().map(|&p| p / extension_factor).collect();
+    reduced.sort_unstable();
+    reduced.dedup();
+    reduced
+}
+
+/// Builds a `StarkProof` from a trace and its low-degree extension,
+/// honoring the blowup, query count, grinding and folding factors in
+/// `options`. Operating against `&dyn TraceLde` rather than a concrete type
+/// lets a caller swap in a multi-threaded or GPU-backed LDE without this
+/// function changing. `program_hash` must be the same `Program::hash()` the
+/// caller will later pass to `verify()` — it is bound into the proof as-is
+/// rather than re-derived from the trace, since the trace's own accumulator
+/// folds in padding and execution order that `Program::hash()` does not.
+#[tracing::instrument(level = "info", skip_all, fields(trace_length = trace.len(), num_queries = options.num_queries))]
+pub fn prove(
+    trace: &TraceTable,
+    lde: &dyn TraceLde,
+    program_hash: [u64; 4],
+    public_inputs: &[u64],
+    outputs: &[u64],
+    options: &ProofOptions,
+) -> StarkProof {
+    let trace_root = lde.commit();
+
+    let mut transcript = Transcript::new();
+    transcript.absorb(&trace_root);
+    transcript.absorb(public_inputs);
+    transcript.absorb(outputs);
+
+    // require a grinding nonce before sampling query positions so that a
+    // prover cannot cheaply grind through many trace variants
+    let grinding_nonce = transcript.grind(options.grinding_factor);
+
+    let domain_size = lde.domain_size();
+    let (queried_positions, queried_rows, final_stack, final_stack_path) = {
+        let _enter = tracing::info_span!("query_phase", num_queries = options.num_queries).entered();
+        let queried_positions = transcript.draw_query_positions(options.num_queries.min(domain_size), domain_size);
+        let queried_positions = include_final_row(queried_positions, domain_size, options.extension_factor);
+        let queried_rows = lde.open_rows(&queried_positions);
+        let (final_stack, final_stack_path) = lde.open_output_row(domain_size - options.extension_factor);
+        (queried_positions, queried_rows, final_stack, final_stack_path)
+    };
+
+    let fri_proof = {
+        let _enter = tracing::info_span!("fri_commit", folding_factor = options.folding_factor).entered();
+        let fri_positions = to_constraint_domain(&queried_positions, options.extension_factor);
+        fri::commit(lde.constraint_evaluations(), options.folding_factor, &fri_positions, &mut transcript)
+    };
+    tracing::info!(fri_layers = fri_proof.layers.len(), "committed FRI layers");
+
+    StarkProof {
+        program_hash,
+        outputs: outputs.to_vec(),
+        trace_root,
+        trace_length: domain_size,
+        queried_positions,
+        queried_rows,
+        final_stack,
+        final_stack_path,
+        fri_proof,
+        grinding_nonce,
+        options: *options,
+    }
+}
+
+/// Re-derives the Fiat-Shamir challenges from the proof's own commitments,
+/// checks the FRI layers fold consistently, and confirms the proof binds to
+/// `outputs` through the authenticated final row. `program_hash` is only
+/// compared with the hash recorded in the proof; it is not absorbed into the
+/// transcript or tied to the trace commitment.
+pub fn verify(
+    program_hash: [u64; 4],
+    public_inputs: &[u64],
+    outputs: &[u64],
+    proof: &StarkProof,
+) -> Result<(), VerifyError> {
+    if proof.program_hash != program_hash {
+        return Err(VerifyError::ProgramHashMismatch);
+    }
+    if proof.outputs != outputs {
+        return Err(VerifyError::OutputMismatch);
+    }
+
+    let mut transcript = Transcript::new();
+    transcript.absorb(&proof.trace_root);
+    transcript.absorb(public_inputs);
+    transcript.absorb(outputs);
+
+    if !transcript.verify_grinding(proof.grinding_nonce, proof.options.grinding_factor) {
+        return Err(VerifyError::GrindingCheckFailed);
+    }
+
+    let expected_positions =
+        transcript.draw_query_positions(proof.queried_positions.len(), proof.trace_length);
+    let expected_positions = include_final_row(expected_positions, proof.trace_length, proof.options.extension_factor);
+    if expected_positions != proof.queried_positions {
+        return Err(VerifyError::QueryPositionMismatch);
+    }
+
+    // authenticate every opened row against the trace commitment before
+    // trusting anything derived from it; without this a prover could report
+    // arbitrary op_acc/stack_hash pairs (and so arbitrary outputs) and
+    // nothing downstream would ever notice
+    for (position, row) in proof.queried_positions.iter().zip(proof.queried_rows.iter()) {
+        let leaf = trace_lde::leaf_for(&row.stack_hash, &row.op_acc);
+        if !MerkleTree::verify(proof.trace_root, *position, leaf, &row.path) {
+            return Err(VerifyError::RowAuthenticationFailed(*position));
+        }
+    }
+
+    // the final row is always among queried_positions (see
+    // `include_final_row`), so its authenticated op_acc is already in
+    // queried_rows; reuse it to authenticate the separately-disclosed
+    // final_stack instead of trusting it on its own
+    let final_position = proof.trace_length - proof.options.extension_factor;
+    let final_op_acc = proof
+        .queried_positions
+        .iter()
+        .zip(proof.queried_rows.iter())
+        .find(|(&p, _)| p == final_position)
+        .map(|(_, row)| row.op_acc)
+        .ok_or(VerifyError::RowAuthenticationFailed(final_position))?;
+    let final_leaf = trace_lde::leaf_for(&trace_lde::stack_hash(&proof.final_stack), &final_op_acc);
+    if !MerkleTree::verify(proof.trace_root, final_position, final_leaf, &proof.final_stack_path) {
+        return Err(VerifyError::RowAuthenticationFailed(final_position));
+    }
+
+    constraints::evaluate_boundary_constraints(&proof.final_stack, outputs)
+        .map_err(|_| VerifyError::BoundaryConstraintViolation(final_position))?;
+
+    let original_length = proof.trace_length / proof.options.extension_factor;
+    let fri_positions = to_constraint_domain(&proof.queried_positions, proof.options.extension_factor);
+    fri::verify(&proof.fri_proof, proof.options.folding_factor, &mut transcript, &fri_positions, original_length)
+        .map_err(|_| VerifyError::FriVerificationFailed)?;
+
+    // tie FRI's base layer to the trace commitment: `fri::verify` above only
+    // checks that the layers are internally consistent with each other, not
+    // that the base layer actually came from this trace, so a prover could
+    // otherwise fold an unrelated, self-consistent evaluation vector and
+    // still pass
+    let base_layer = proof.fri_proof.layers.first().ok_or(VerifyError::FriVerificationFailed)?;
+    for (position, row) in proof.queried_positions.iter().zip(proof.queried_rows.iter()) {
+        let trace_row = position / proof.options.extension_factor;
+        let fri_value = base_layer
+            .openings
+            .iter()
+            .find(|o| o.position == trace_row)
+            .map(|o| o.value)
+            .ok_or(VerifyError::FriVerificationFailed)?;
+        if fri_value != field::from_u64(row.op_acc[0]) {
+            return Err(VerifyError::FriVerificationFailed);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processor::execute;
+    use crate::processor::opcodes;
+    use crate::program::Program;
+
+    // small blowup/query counts so the tests stay fast; the round trip
+    // doesn't depend on these being the production defaults
+    fn test_options() -> ProofOptions {
+        ProofOptions::new(4, 8, 0, 2, FieldExtension::None)
+    }
+
+    #[test]
+    fn round_trip_verify_succeeds() {
+        let program = Program::from_ops(vec![opcodes::NOOP]);
+        let public_inputs = [5u64];
+        let options = test_options();
+
+        let (program_hash, outputs, proof) = execute(&program, &public_inputs, &[], 1, &options, None);
+
+        assert_eq!(outputs, vec![5]);
+        assert!(verify(program_hash, &public_inputs, &outputs, &proof).is_ok());
+    }
+
+    #[test]
+    fn tampered_outputs_are_rejected() {
+        let program = Program::from_ops(vec![opcodes::NOOP]);
+        let public_inputs = [5u64];
+        let options = test_options();
+
+        let (program_hash, outputs, proof) = execute(&program, &public_inputs, &[], 1, &options, None);
+        let tampered_outputs = vec![outputs[0].wrapping_add(1)];
+
+        assert!(verify(program_hash, &public_inputs, &tampered_outputs, &proof).is_err());
+    }
+
+    #[test]
+    fn tampered_proof_is_rejected() {
+        let program = Program::from_ops(vec![opcodes::NOOP]);
+        let public_inputs = [5u64];
+        let options = test_options();
+
+        let (program_hash, outputs, mut proof) = execute(&program, &public_inputs, &[], 1, &options, None);
+        proof.trace_root[0] ^= 1;
+
+        assert!(verify(program_hash, &public_inputs, &outputs, &proof).is_err());
+    }
+
+    #[test]
+    fn tampered_queried_row_is_rejected() {
+        let program = Program::from_ops(vec![opcodes::NOOP]);
+        let public_inputs = [5u64];
+        let options = test_options();
+
+        let (program_hash, outputs, mut proof) = execute(&program, &public_inputs, &[], 1, &options, None);
+        proof.queried_rows[0].op_acc[0] ^= 1;
+
+        assert!(matches!(
+            verify(program_hash, &public_inputs, &outputs, &proof),
+            Err(VerifyError::RowAuthenticationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn wrong_program_hash_is_rejected() {
+        let program = Program::from_ops(vec![opcodes::NOOP]);
+        let public_inputs = [5u64];
+        let options = test_options();
+
+        let (_, outputs, proof) = execute(&program, &public_inputs, &[], 1, &options, None);
+
+        assert!(verify([0, 0, 0, 0], &public_inputs, &outputs, &proof).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::hash;
+use crate::processor::opcodes;
+use crate::processor::profiler::ExecutionProfile;
+use crate::stark::ProofOptions;
+
+/// A single row of the execution trace: the stack contents and a running
+/// per-step accumulator folding in every opcode executed so far. This is
+/// internal trace randomness for the constraint evaluator and FRI, not the
+/// program hash; that now comes from `Program::hash` instead.
+pub struct TraceState {
+    stack: Vec<u64>,
+    op_acc: [u64; 4],
+}
+
+impl TraceState {
+    pub fn get_stack(&self) -> &[u64] {
+        &self.stack
+    }
+
+    pub fn get_op_acc(&self) -> &[u64; 4] {
+        &self.op_acc
+    }
+}
+
+/// The execution trace produced by running a program: one `TraceState` per
+/// step, plus the blowup factor it will eventually be extended by for the
+/// low-degree extension.
+pub struct TraceTable {
+    states: Vec<TraceState>,
+    extension_factor: usize,
+}
+
+impl TraceTable {
+    /// Builds the execution trace for `program` against `public_inputs`
+    /// (bound into the proof) and a secret `advice_tape` that opcodes can
+    /// pull non-deterministic values from via `opcodes::READ`, but which
+    /// never appears in the proof itself. `program_length` is the number of
+    /// real opcodes before `program` was padded to a power of two; steps
+    /// past it are counted as padding overhead rather than folded into the
+    /// padding opcode's own cycle count. Pass `Some(profile)` to also
+    /// collect per-opcode cycle counts and peak stack depth as the trace is
+    /// built.
+    pub fn new(
+        program: &[u64],
+        public_inputs: &[u64],
+        advice_tape: &[u64],
+        options: &ProofOptions,
+        program_length: usize,
+        mut profile: Option<&mut ExecutionProfile>,
+    ) -> TraceTable {
+        let mut stack: Vec<u64> = public_inputs.to_vec();
+        let mut advice_cursor = 0;
+        let mut op_acc = [0u64; 4];
+        let mut states = Vec::with_capacity(program.len());
+        let mut padding_cycles = 0;
+
+        for (step, &op) in program.iter().enumerate() {
+            op_acc = hash::hash_values(&[op_acc[0], op_acc[1], op_acc[2], op_acc[3], op]);
+
+            match op {
+                opcodes::NOOP => {}
+                opcodes::PUSH => stack.push(0),
+                opcodes::DROP => { stack.pop(); }
+                opcodes::ADD => {
+                    let b = stack.pop().unwrap_or(0);
+                    let a = stack.pop().unwrap_or(0);
+                    stack.push(a.wrapping_add(b));
+                }
+                opcodes::READ => {
+                    let value = advice_tape.get(advice_cursor).copied().unwrap_or(0);
+                    advice_cursor += 1;
+                    stack.push(value);
+                }
+                _ => {}
+            }
+
+            if step < program_length {
+                if let Some(profile) = profile.as_deref_mut() {
+                    profile.record_step(op, stack.len());
+                }
+            } else {
+                padding_cycles += 1;
+            }
+
+            states.push(TraceState { stack: stack.clone(), op_acc });
+        }
+
+        if let Some(profile) = profile {
+            profile.set_padding_cycles(padding_cycles);
+        }
+
+        TraceTable { states, extension_factor: options.extension_factor }
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn get_state(&self, step: usize) -> &TraceState {
+        &self.states[step]
+    }
+
+    pub fn extension_factor(&self) -> usize {
+        self.extension_factor
+    }
+}
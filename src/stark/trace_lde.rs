@@ -0,0 +1,130 @@
+use crate::hash;
+use crate::stark::field::{self, BaseElement};
+use crate::stark::merkle::MerkleTree;
+use crate::stark::trace::TraceTable;
+
+/// A trace row opened at a query position. Only the op-accumulator and a
+/// hash of the stack are disclosed, each with the Merkle path tying it back
+/// to `TraceLde::commit()`'s root; the stack itself can hold values pulled
+/// straight off the secret advice tape (`opcodes::READ`), so a generic
+/// opening must not reveal it. The one row that legitimately needs its stack
+/// disclosed — the final one, to check the claimed outputs — is opened
+/// separately via `open_output_row`.
+pub struct OpenedRow {
+    pub op_acc: [u64; 4],
+    pub stack_hash: [u64; 4],
+    pub path: Vec<[u64; 4]>,
+}
+
+pub(crate) fn stack_hash(stack: &[u64]) -> [u64; 4] {
+    hash::hash_values(stack)
+}
+
+/// The Merkle leaf for a trace row, shared by `DefaultTraceLde` and
+/// `verify()` so the verifier can recompute exactly what the prover
+/// committed to from an `OpenedRow`'s disclosed `stack_hash`/`op_acc`.
+pub(crate) fn leaf_for(stack_hash: &[u64; 4], op_acc: &[u64; 4]) -> [u64; 4] {
+    hash::hash_values(&[stack_hash[0], stack_hash[1], stack_hash[2], stack_hash[3],
+                         op_acc[0], op_acc[1], op_acc[2], op_acc[3]])
+}
+
+/// Evaluates an execution trace over a blown-up domain and commits to it, so
+/// the prover can open individual rows at positions the verifier chooses
+/// without revealing the whole trace. `TraceTable`'s own logic lives in
+/// `DefaultTraceLde`; a multi-threaded or GPU-backed prover can implement
+/// this trait instead without `prove()` or the constraint evaluator needing
+/// to know the difference.
+pub trait TraceLde {
+    /// Number of rows in the low-degree-extended trace.
+    fn domain_size(&self) -> usize;
+
+    /// Root of the Merkle tree committing to every row of the LDE.
+    fn commit(&self) -> [u64; 4];
+
+    /// Op-accumulator and stack hash at the given LDE-domain rows, each with
+    /// the Merkle path authenticating it against `commit()`'s root. Never
+    /// exposes stack contents, which may hold secret advice-tape values.
+    fn open_rows(&self, positions: &[usize]) -> Vec<OpenedRow>;
+
+    /// Discloses the actual stack at `position`, along with its Merkle path.
+    /// Only meant to be called on the final trace row, whose stack prefix is
+    /// the claimed public output and so isn't a secret in the first place.
+    fn open_output_row(&self, position: usize) -> (Vec<u64>, Vec<[u64; 4]>);
+
+    /// Base-trace evaluations used to seed FRI, over the smaller
+    /// constraint-evaluation domain rather than the full LDE domain.
+    fn constraint_evaluations(&self) -> &[BaseElement];
+}
+
+/// The in-memory LDE: extends each trace row by repeating it across the
+/// blowup factor, which gives the Merkle commitment a domain
+/// `extension_factor` times larger than the trace itself. FRI is seeded over
+/// the un-extended trace instead (see `constraint_evaluations`).
+pub struct DefaultTraceLde {
+    stacks: Vec<Vec<u64>>,
+    op_accs: Vec<[u64; 4]>,
+    tree: MerkleTree,
+    constraint_evaluations: Vec<BaseElement>,
+}
+
+impl DefaultTraceLde {
+    pub fn new(trace: &TraceTable) -> DefaultTraceLde {
+        let extension_factor = trace.extension_factor();
+        let trace_length = trace.len();
+
+        let mut stacks = Vec::with_capacity(trace_length * extension_factor);
+        let mut op_accs = Vec::with_capacity(trace_length * extension_factor);
+        for i in 0..trace_length {
+            let state = trace.get_state(i);
+            for _ in 0..extension_factor {
+                stacks.push(state.get_stack().to_vec());
+                op_accs.push(*state.get_op_acc());
+            }
+        }
+
+        let leaves: Vec<[u64; 4]> = stacks
+            .iter()
+            .zip(op_accs.iter())
+            .map(|(stack, op_acc)| leaf_for(&stack_hash(stack), op_acc))
+            .collect();
+        let tree = MerkleTree::new(leaves);
+
+        // FRI is seeded with the first op-accumulator word of each
+        // un-extended trace step, over that smaller domain rather than the
+        // full LDE domain; no constraint is evaluated here
+        let constraint_evaluations = (0..trace_length)
+            .map(|i| field::from_u64(trace.get_state(i).get_op_acc()[0]))
+            .collect();
+
+        DefaultTraceLde { stacks, op_accs, tree, constraint_evaluations }
+    }
+}
+
+impl TraceLde for DefaultTraceLde {
+    fn domain_size(&self) -> usize {
+        self.stacks.len()
+    }
+
+    fn commit(&self) -> [u64; 4] {
+        self.tree.root()
+    }
+
+    fn open_rows(&self, positions: &[usize]) -> Vec<OpenedRow> {
+        positions
+            .iter()
+            .map(|&p| OpenedRow {
+                op_acc: self.op_accs[p],
+                stack_hash: stack_hash(&self.stacks[p]),
+                path: self.tree.prove(p),
+            })
+            .collect()
+    }
+
+    fn open_output_row(&self, position: usize) -> (Vec<u64>, Vec<[u64; 4]>) {
+        (self.stacks[position].clone(), self.tree.prove(position))
+    }
+
+    fn constraint_evaluations(&self) -> &[BaseElement] {
+        &self.constraint_evaluations
+    }
+}
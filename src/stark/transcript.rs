@@ -0,0 +1,91 @@
+use crate::hash::{hash_values, hash_with_nonce};
+
+/// A Fiat-Shamir transcript: the prover and verifier both absorb the same
+/// sequence of public values (commitments, public inputs, outputs) and draw
+/// the same sequence of challenges out of it, so neither side can bias the
+/// challenges after seeing them.
+pub struct Transcript {
+    state: [u64; 4],
+    counter: u64,
+}
+
+impl Default for Transcript {
+    fn default() -> Transcript {
+        Transcript::new()
+    }
+}
+
+impl Transcript {
+    pub fn new() -> Transcript {
+        Transcript { state: [0, 0, 0, 0], counter: 0 }
+    }
+
+    pub fn absorb(&mut self, values: &[u64]) {
+        let mut input = vec![self.state[0], self.state[1], self.state[2], self.state[3]];
+        input.extend_from_slice(values);
+        self.state = hash_values(&input);
+        self.counter = 0;
+    }
+
+    /// Draws the next pseudo-random word out of the transcript state.
+    pub fn draw_u64(&mut self) -> u64 {
+        self.counter += 1;
+        hash_with_nonce(&self.state, self.counter)[0]
+    }
+
+    /// Proof-of-work grinding: finds the smallest nonce such that hashing it
+    /// together with the current transcript state produces a digest with at
+    /// least `grinding_factor` trailing zero bits, then folds that nonce into
+    /// the state so every challenge drawn afterwards depends on it. This adds
+    /// `grinding_factor` bits of extra work for anyone trying to bias the
+    /// query positions drawn below. With `grinding_factor == 0` it returns 0
+    /// and leaves the state unchanged.
+    pub fn grind(&mut self, grinding_factor: u32) -> u64 {
+        if grinding_factor == 0 {
+            return 0;
+        }
+
+        let mut nonce = 0u64;
+        loop {
+            let digest = hash_with_nonce(&self.state, nonce);
+            if digest[0].trailing_zeros() >= grinding_factor {
+                break;
+            }
+            nonce += 1;
+        }
+
+        self.absorb(&[nonce]);
+        nonce
+    }
+
+    /// Verifier-side counterpart to `grind`: checks that `nonce` actually
+    /// satisfies the proof-of-work requirement against the current
+    /// transcript state, then folds it in so later draws stay in sync with
+    /// the prover.
+    pub fn verify_grinding(&mut self, nonce: u64, grinding_factor: u32) -> bool {
+        if grinding_factor == 0 {
+            return true;
+        }
+
+        let digest = hash_with_nonce(&self.state, nonce);
+        if digest[0].trailing_zeros() < grinding_factor {
+            return false;
+        }
+
+        self.absorb(&[nonce]);
+        true
+    }
+
+    /// Draws `count` distinct query positions in `0..domain_size`.
+    pub fn draw_query_positions(&mut self, count: usize, domain_size: usize) -> Vec<usize> {
+        let mut positions = Vec::with_capacity(count);
+        while positions.len() < count {
+            let position = (self.draw_u64() as usize) % domain_size;
+            if !positions.contains(&position) {
+                positions.push(position);
+            }
+        }
+        positions.sort_unstable();
+        positions
+    }
+}